@@ -1,13 +1,17 @@
 use {
     crate::rpc_loader::load_address_lookup_tables,
     serde::{Deserialize, Serialize},
+    solana_client::rpc_client::RpcClient,
     solana_sdk::{
         address_lookup_table::state::AddressLookupTable,
+        clock::Slot,
+        commitment_config::{CommitmentConfig, CommitmentLevel},
         message::{
             v0::{LoadedAddresses, MessageAddressTableLookup},
             AddressLoaderError,
         },
         pubkey::Pubkey,
+        slot_hashes::MAX_ENTRIES as DEACTIVATION_GRACE_SLOTS,
         transaction::AddressLoader,
     },
     std::{
@@ -16,56 +20,439 @@ use {
         fs::File,
         io::{BufReader, BufWriter},
         path::{Path, PathBuf},
+        sync::Arc,
     },
 };
 
+/// Default endpoint used when no RPC client is supplied.
+const DEFAULT_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+
+fn default_commitment() -> CommitmentConfig {
+    CommitmentConfig {
+        commitment: CommitmentLevel::Finalized,
+    }
+}
+
+/// Prometheus-style instrumentation for embedding `Store` in a long-running
+/// indexer, enabled with the `metrics` feature.
+#[cfg(feature = "metrics")]
+pub mod metrics {
+    use {
+        once_cell::sync::Lazy,
+        prometheus::{
+            Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+        },
+    };
+
+    static REGISTRY: Lazy<Registry> = Lazy::new(Registry::new);
+
+    /// Number of ALT tables currently held in the store.
+    pub static ALTS_IN_STORE: Lazy<IntGauge> = Lazy::new(|| {
+        let gauge = IntGauge::new(
+            "alt_store_alts_in_store",
+            "Number of ALT tables currently held in the store",
+        )
+        .unwrap();
+        REGISTRY.register(Box::new(gauge.clone())).unwrap();
+        gauge
+    });
+
+    /// Tables enqueued in the background loader's queue but not yet fetched.
+    pub static LOADER_QUEUE_DEPTH: Lazy<IntGauge> = Lazy::new(|| {
+        let gauge = IntGauge::new(
+            "alt_store_loader_queue_depth",
+            "Tables pending fetch in the background loader's queue",
+        )
+        .unwrap();
+        REGISTRY.register(Box::new(gauge.clone())).unwrap();
+        gauge
+    });
+
+    /// Cumulative number of RPC `get_multiple_accounts` calls made to fetch ALTs.
+    pub static RPC_FETCH_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+        let counter = IntCounter::new(
+            "alt_store_rpc_fetch_count",
+            "Cumulative number of RPC calls made to fetch ALTs",
+        )
+        .unwrap();
+        REGISTRY.register(Box::new(counter.clone())).unwrap();
+        counter
+    });
+
+    /// Cumulative latency of RPC `get_multiple_accounts` calls, in seconds.
+    pub static RPC_FETCH_LATENCY_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+        let histogram = Histogram::with_opts(HistogramOpts::new(
+            "alt_store_rpc_fetch_latency_seconds",
+            "Latency of RPC calls made to fetch ALTs",
+        ))
+        .unwrap();
+        REGISTRY.register(Box::new(histogram.clone())).unwrap();
+        histogram
+    });
+
+    /// Counts of each `AddressLoaderError` variant returned by
+    /// `load_addresses`, labeled by variant name.
+    pub static LOAD_ADDRESSES_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
+        let counter = IntCounterVec::new(
+            Opts::new(
+                "alt_store_load_addresses_errors_total",
+                "Count of AddressLoaderError returned by load_addresses, by variant",
+            ),
+            &["variant"],
+        )
+        .unwrap();
+        REGISTRY.register(Box::new(counter.clone())).unwrap();
+        counter
+    });
+
+    pub(crate) fn record_load_addresses_error(variant: &str) {
+        LOAD_ADDRESSES_ERRORS.with_label_values(&[variant]).inc();
+    }
+
+    /// Render all registered metrics in Prometheus text exposition format, so
+    /// an operator embedding this store can scrape or alarm on them.
+    pub fn gather() -> String {
+        use prometheus::Encoder;
+
+        let encoder = prometheus::TextEncoder::new();
+        let mut buffer = Vec::new();
+        encoder
+            .encode(&REGISTRY.gather(), &mut buffer)
+            .expect("metrics encode to a growable buffer cannot fail");
+        String::from_utf8(buffer).expect("prometheus text encoding is always valid utf8")
+    }
+}
+
 /// Store for ALT data by Pubkey.
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct Store {
     path: PathBuf,
     inner: StoreInner,
+    persist_mode: PersistMode,
+    /// Pubkeys written since the last successful save. Used in
+    /// [`PersistMode::Journal`] to append only what's new instead of diffing
+    /// the whole map.
+    dirty: Vec<Pubkey>,
+    rpc_client: Arc<RpcClient>,
+}
+
+impl std::fmt::Debug for Store {
+    // `RpcClient` doesn't implement `Debug`, so it's omitted rather than
+    // derived away entirely.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Store")
+            .field("path", &self.path)
+            .field("inner", &self.inner)
+            .field("persist_mode", &self.persist_mode)
+            .field("dirty", &self.dirty)
+            .finish_non_exhaustive()
+    }
+}
+
+/// How `Store::save_to_path` writes changes to disk.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PersistMode {
+    /// Rewrite the whole map on every save. The write goes to a sibling temp
+    /// file followed by an atomic rename, so a crash mid-write can never
+    /// leave the store file truncated or partially written.
+    #[default]
+    Snapshot,
+    /// Append only newly-written `(Pubkey, write_version, data)` records.
+    /// Avoids rewriting the whole map when bulk-ingesting thousands of
+    /// tables; the latest state is reconstructed on load by keeping the
+    /// highest `write_version` seen per pubkey.
+    Journal,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
-struct StoreInner(HashMap<Pubkey, Vec<u8>>);
+struct StoreInner {
+    /// Monotonically increasing counter, bumped on every insert. Used as the
+    /// per-record version in journal mode, so replaying the log can keep
+    /// only the highest version seen per pubkey and discard superseded
+    /// writes. Also checked against the highest per-record version on load
+    /// (see [`StoreInner::validate`]), which rejects a file whose header is
+    /// behind its own records; a complete copy of an older store passes.
+    write_version: u64,
+    /// Raw account data paired with the write version it was inserted at,
+    /// kept around so the store can be re-serialized without a round-trip
+    /// through the RPC.
+    raw: HashMap<Pubkey, (u64, Vec<u8>)>,
+    /// Deserialized addresses for each table, so `load_addresses` doesn't have
+    /// to re-parse the raw bytes on every lookup. Rebuilt from `raw` on load.
+    #[serde(skip)]
+    parsed: HashMap<Pubkey, Arc<Vec<Pubkey>>>,
+    /// Freshness/liveness metadata for each table, so `Store::update` in
+    /// `UpdateMode::Refresh` can tell a stale cached table from a current one
+    /// without re-fetching addresses it already has. Rebuilt from `raw` on
+    /// load, same as `parsed`.
+    #[serde(skip)]
+    meta: HashMap<Pubkey, TableMeta>,
+}
+
+/// Freshness/liveness snapshot of a table's `AddressLookupTable::meta`,
+/// captured whenever the table is inserted.
+#[derive(Clone, Copy, Debug, Default)]
+struct TableMeta {
+    last_extended_slot: Slot,
+    deactivation_slot: Slot,
+    address_count: usize,
+}
+
+/// A single append-only journal entry, as written in [`PersistMode::Journal`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct JournalRecord {
+    pubkey: Pubkey,
+    write_version: u64,
+    data: Vec<u8>,
+}
+
+impl StoreInner {
+    fn new() -> Self {
+        Self {
+            write_version: 0,
+            raw: HashMap::new(),
+            parsed: HashMap::new(),
+            meta: HashMap::new(),
+        }
+    }
+
+    /// Rebuild the parsed-address and freshness-metadata caches from raw
+    /// bytes, e.g. after loading from disk.
+    fn rebuild_parsed_cache(&mut self) {
+        self.parsed.clear();
+        self.meta.clear();
+        for (pubkey, (_, data)) in &self.raw {
+            let Ok(alt) = AddressLookupTable::deserialize(data) else {
+                continue;
+            };
+            self.parsed.insert(*pubkey, Arc::new(alt.addresses.to_vec()));
+            self.meta.insert(
+                *pubkey,
+                TableMeta {
+                    last_extended_slot: alt.meta.last_extended_slot,
+                    deactivation_slot: alt.meta.deactivation_slot,
+                    address_count: alt.addresses.len(),
+                },
+            );
+        }
+    }
+
+    /// Check that the header `write_version` is at least as high as the
+    /// highest per-record version actually present, rejecting a file whose
+    /// records are newer than its header. A complete copy of an older store
+    /// passes, and in journal mode, where the header is rebuilt from the
+    /// records, the check always passes.
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        let max_seen = self.raw.values().map(|(version, _)| *version).max().unwrap_or(0);
+        if self.write_version < max_seen {
+            return Err(format!(
+                "store header write_version ({}) is behind the highest per-record \
+                 write_version ({max_seen}); file may be truncated or a stale copy",
+                self.write_version,
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// Path of the sibling temp file `save_to_path` stages a snapshot write in
+/// before atomically renaming it over `path`.
+fn tmp_sibling_path(path: &Path) -> PathBuf {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{file_name}.tmp"))
+}
+
+/// Is this a `bincode` EOF error, i.e. "no more records to read", as opposed
+/// to an actually corrupt record? A trailing record cut off part-way also
+/// reports EOF, so it is treated as the end of the journal and dropped.
+fn is_eof(err: &bincode::Error) -> bool {
+    matches!(
+        &**err,
+        bincode::ErrorKind::Io(io_err) if io_err.kind() == std::io::ErrorKind::UnexpectedEof
+    )
+}
 
 // IO operations
 impl Store {
     pub fn load_or_create(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        Self::load_or_create_with_rpc(path, DEFAULT_RPC_URL.to_string(), default_commitment())
+    }
+
+    /// Like [`Store::load_or_create`], but with explicit control over how
+    /// saves are persisted.
+    pub fn load_or_create_with_mode(
+        path: impl AsRef<Path>,
+        persist_mode: PersistMode,
+    ) -> Result<Self, Box<dyn Error>> {
+        let rpc_client = Arc::new(RpcClient::new_with_commitment(
+            DEFAULT_RPC_URL.to_string(),
+            default_commitment(),
+        ));
+        Self::load_or_create_with_mode_and_rpc_client(path, persist_mode, rpc_client)
+    }
+
+    /// Like [`Store::load_or_create`], but against the given endpoint and
+    /// commitment level instead of mainnet-beta at `Finalized`.
+    pub fn load_or_create_with_rpc(
+        path: impl AsRef<Path>,
+        url: String,
+        commitment: CommitmentConfig,
+    ) -> Result<Self, Box<dyn Error>> {
+        Self::load_or_create_with_rpc_client(
+            path,
+            Arc::new(RpcClient::new_with_commitment(url, commitment)),
+        )
+    }
+
+    /// Like [`Store::load_or_create`], but against a caller-supplied RPC
+    /// client. Useful for pointing at devnet/testnet/a local validator, or
+    /// injecting a mock client in tests.
+    pub fn load_or_create_with_rpc_client(
+        path: impl AsRef<Path>,
+        rpc_client: Arc<RpcClient>,
+    ) -> Result<Self, Box<dyn Error>> {
+        Self::load_or_create_with_mode_and_rpc_client(path, PersistMode::default(), rpc_client)
+    }
+
+    /// Fully explicit constructor combining a [`PersistMode`] and an RPC
+    /// client; the other `load_or_create_*` constructors delegate here.
+    pub fn load_or_create_with_mode_and_rpc_client(
+        path: impl AsRef<Path>,
+        persist_mode: PersistMode,
+        rpc_client: Arc<RpcClient>,
+    ) -> Result<Self, Box<dyn Error>> {
         let path = path.as_ref().to_path_buf();
         if path.exists() {
-            Self::load_from_path(path)
+            Self::load_from_path(path, persist_mode, rpc_client)
         } else {
-            Self::new_with_path(path)
+            Self::new_with_path(path, persist_mode, rpc_client)
         }
     }
 
     /// Create a new Store at the given path, assuming it does not already exist.
-    fn new_with_path(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+    fn new_with_path(
+        path: impl AsRef<Path>,
+        persist_mode: PersistMode,
+        rpc_client: Arc<RpcClient>,
+    ) -> Result<Self, Box<dyn Error>> {
         std::fs::write(path.as_ref(), &[])?; // Create the file
         Ok(Self {
             path: path.as_ref().to_path_buf(),
-            inner: StoreInner(HashMap::new()),
+            inner: StoreInner::new(),
+            persist_mode,
+            dirty: Vec::new(),
+            rpc_client,
         })
     }
 
     /// Load a Store from the given path, assuming it already exists.
-    fn load_from_path(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
-        Ok(Self {
-            path: path.as_ref().to_path_buf(),
-            inner: {
+    fn load_from_path(
+        path: impl AsRef<Path>,
+        persist_mode: PersistMode,
+        rpc_client: Arc<RpcClient>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref().to_path_buf();
+        let mut inner = match persist_mode {
+            PersistMode::Snapshot => {
                 let file = File::open(&path)?;
                 let reader = BufReader::new(file);
                 bincode::deserialize_from(reader)?
-            },
+            }
+            PersistMode::Journal => Self::load_journal(&path)?,
+        };
+        inner.validate()?;
+        inner.rebuild_parsed_cache();
+
+        #[cfg(feature = "metrics")]
+        metrics::ALTS_IN_STORE.set(inner.raw.len() as i64);
+
+        Ok(Self {
+            path,
+            inner,
+            persist_mode,
+            dirty: Vec::new(),
+            rpc_client,
+        })
+    }
+
+    /// Replay an append-only journal file, keeping only the highest
+    /// `write_version` seen per pubkey.
+    fn load_journal(path: &Path) -> Result<StoreInner, Box<dyn Error>> {
+        let file = File::open(path)?;
+        let mut reader = BufReader::new(file);
+        let mut raw: HashMap<Pubkey, (u64, Vec<u8>)> = HashMap::new();
+
+        loop {
+            match bincode::deserialize_from::<_, JournalRecord>(&mut reader) {
+                Ok(record) => {
+                    raw.entry(record.pubkey)
+                        .and_modify(|(version, data)| {
+                            if record.write_version > *version {
+                                *version = record.write_version;
+                                *data = record.data.clone();
+                            }
+                        })
+                        .or_insert((record.write_version, record.data));
+                }
+                Err(err) if is_eof(&err) => break,
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        let write_version = raw.values().map(|(version, _)| *version).max().unwrap_or(0);
+        Ok(StoreInner {
+            write_version,
+            raw,
+            parsed: HashMap::new(),
+            meta: HashMap::new(),
         })
     }
 
-    /// Save the Store to disk.
-    pub fn save_to_path(&self) -> Result<(), Box<dyn Error>> {
-        let file = File::options().write(true).append(false).open(&self.path)?;
-        let writer = BufWriter::new(file);
-        bincode::serialize_into(writer, &self.inner)?;
+    /// Save the Store to disk, per its configured [`PersistMode`]. Takes
+    /// `&mut self` because a successful save clears the dirty list, so
+    /// [`PersistMode::Journal`] doesn't re-append pubkeys it already wrote.
+    pub fn save_to_path(&mut self) -> Result<(), Box<dyn Error>> {
+        match self.persist_mode {
+            PersistMode::Snapshot => self.save_snapshot()?,
+            PersistMode::Journal => self.append_journal()?,
+        }
+        self.dirty.clear();
+        Ok(())
+    }
+
+    /// Write the whole map to a sibling temp file and atomically rename it
+    /// over `path`, so a crash mid-write leaves the previous file intact.
+    fn save_snapshot(&self) -> Result<(), Box<dyn Error>> {
+        let tmp_path = tmp_sibling_path(&self.path);
+        {
+            let file = File::create(&tmp_path)?;
+            let writer = BufWriter::new(file);
+            bincode::serialize_into(writer, &self.inner)?;
+        }
+        std::fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// Append a journal record for every pubkey written since the last save.
+    fn append_journal(&self) -> Result<(), Box<dyn Error>> {
+        let file = File::options()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let mut writer = BufWriter::new(file);
+        for pubkey in &self.dirty {
+            if let Some((write_version, data)) = self.inner.raw.get(pubkey) {
+                bincode::serialize_into(
+                    &mut writer,
+                    &JournalRecord {
+                        pubkey: *pubkey,
+                        write_version: *write_version,
+                        data: data.clone(),
+                    },
+                )?;
+            }
+        }
         Ok(())
     }
 }
@@ -78,13 +465,39 @@ pub enum UpdateMode {
     Append,
     /// Update store with new data, regardless of existing data.
     Overwrite,
+    /// Re-fetch every requested table and only overwrite the cached entry
+    /// if the on-chain table has actually changed since: it was extended
+    /// (`last_extended_slot` advanced) or its `deactivation_slot` changed.
+    /// Tables already present but unchanged on-chain are left alone.
+    Refresh,
 }
 
 // Update operations
 impl Store {
     /// Check if the Store contains data for the given Pubkey.
     pub fn contains_key(&self, pubkey: &Pubkey) -> bool {
-        self.inner.0.contains_key(pubkey)
+        self.inner.raw.contains_key(pubkey)
+    }
+
+    /// Render the `metrics` feature's Prometheus counters in text exposition
+    /// format, for an operator to scrape or alarm on.
+    #[cfg(feature = "metrics")]
+    pub fn metrics_text() -> String {
+        metrics::gather()
+    }
+
+    /// Whether the cached table is still usable for lookups at `current_slot`:
+    /// it either was never deactivated, or was deactivated recently enough
+    /// that the runtime would still resolve it. Returns `false` for tables
+    /// not present in the store. Mirrors the grace period the Solana runtime
+    /// grants a deactivated lookup table before it becomes purgeable.
+    pub fn is_active(&self, pubkey: &Pubkey, current_slot: Slot) -> bool {
+        let Some(meta) = self.inner.meta.get(pubkey) else {
+            return false;
+        };
+        meta.deactivation_slot == Slot::MAX
+            || current_slot.saturating_sub(meta.deactivation_slot)
+                < DEACTIVATION_GRACE_SLOTS as Slot
     }
 
     /// Fetch and update the Store with new data for the given Pubkeys.
@@ -99,23 +512,89 @@ impl Store {
                 .filter(|pubkey| !self.contains_key(pubkey))
                 .cloned()
                 .collect(),
-            UpdateMode::Overwrite => pubkeys.to_vec(),
+            UpdateMode::Overwrite | UpdateMode::Refresh => pubkeys.to_vec(),
         };
 
         if !fetch_pubkeys.is_empty() {
-            let fetched_alt_data = load_address_lookup_tables(&fetch_pubkeys)?;
+            let fetched_alt_data = load_address_lookup_tables(&self.rpc_client, &fetch_pubkeys)?;
+            let mut changed = false;
             for (pubkey, data) in fetched_alt_data {
+                if matches!(update_mode, UpdateMode::Refresh) && !self.is_stale(&pubkey, &data) {
+                    continue;
+                }
                 self.insert_table_data(pubkey, data);
+                changed = true;
+            }
+            if changed {
+                self.save_to_path()?;
             }
-            self.save_to_path()?;
         }
 
         Ok(())
     }
 
-    /// Insert new data into the Store.
+    /// In `UpdateMode::Refresh`, whether a freshly-fetched table's on-chain
+    /// meta differs from what's cached, i.e. whether it's worth overwriting.
+    fn is_stale(&self, pubkey: &Pubkey, data: &[u8]) -> bool {
+        let Ok(alt) = AddressLookupTable::deserialize(data) else {
+            return false;
+        };
+        match self.inner.meta.get(pubkey) {
+            Some(cached) => {
+                alt.meta.last_extended_slot > cached.last_extended_slot
+                    || alt.meta.deactivation_slot != cached.deactivation_slot
+            }
+            None => true,
+        }
+    }
+
+    /// Insert new data into the Store, parsing the addresses once so later
+    /// lookups don't have to re-deserialize the raw account data.
     fn insert_table_data(&mut self, pubkey: Pubkey, data: Vec<u8>) {
-        self.inner.0.insert(pubkey, data);
+        self.inner.write_version += 1;
+        let write_version = self.inner.write_version;
+
+        if let Ok(alt) = AddressLookupTable::deserialize(&data) {
+            self.inner.meta.insert(
+                pubkey,
+                TableMeta {
+                    last_extended_slot: alt.meta.last_extended_slot,
+                    deactivation_slot: alt.meta.deactivation_slot,
+                    address_count: alt.addresses.len(),
+                },
+            );
+            self.inner
+                .parsed
+                .insert(pubkey, Arc::new(alt.addresses.to_vec()));
+        } else {
+            // Don't keep serving addresses parsed from data this overwrites.
+            self.inner.parsed.remove(&pubkey);
+            self.inner.meta.remove(&pubkey);
+        }
+
+        let _is_new_entry = self.inner.raw.insert(pubkey, (write_version, data)).is_none();
+        #[cfg(feature = "metrics")]
+        if _is_new_entry {
+            metrics::ALTS_IN_STORE.inc();
+        }
+
+        self.dirty.push(pubkey);
+    }
+
+    /// Insert a batch of freshly-fetched table data and persist it once,
+    /// rather than once per table. Used by the background loader so a whole
+    /// coalesced burst of fetches results in a single `save_to_path` call.
+    pub(crate) fn insert_batch(
+        &mut self,
+        entries: Vec<(Pubkey, Vec<u8>)>,
+    ) -> Result<(), Box<dyn Error>> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        for (pubkey, data) in entries {
+            self.insert_table_data(pubkey, data);
+        }
+        self.save_to_path()
     }
 }
 
@@ -128,30 +607,210 @@ impl AddressLoader for &Store {
         let mut readonly = vec![];
 
         for lookup in lookups {
-            let Some(data) = self.inner.0.get(&lookup.account_key) else {
-                return Err(AddressLoaderError::LookupTableAccountNotFound);
+            let addresses = match self.inner.parsed.get(&lookup.account_key) {
+                Some(addresses) => addresses,
+                // Present in the store but failed to parse at insert time.
+                None if self.inner.raw.contains_key(&lookup.account_key) => {
+                    #[cfg(feature = "metrics")]
+                    metrics::record_load_addresses_error("InvalidAccountData");
+                    return Err(AddressLoaderError::InvalidAccountData);
+                }
+                None => {
+                    #[cfg(feature = "metrics")]
+                    metrics::record_load_addresses_error("LookupTableAccountNotFound");
+                    return Err(AddressLoaderError::LookupTableAccountNotFound);
+                }
             };
 
-            let alt = AddressLookupTable::deserialize(data)
-                .map_err(|_| AddressLoaderError::InvalidAccountData)?;
-
             for index in &lookup.writable_indexes {
-                writable.push(
-                    *alt.addresses
-                        .get(*index as usize)
-                        .ok_or(AddressLoaderError::InvalidLookupIndex)?,
-                );
+                let Some(address) = addresses.get(*index as usize) else {
+                    #[cfg(feature = "metrics")]
+                    metrics::record_load_addresses_error("InvalidLookupIndex");
+                    return Err(AddressLoaderError::InvalidLookupIndex);
+                };
+                writable.push(*address);
             }
 
             for index in &lookup.readonly_indexes {
-                readonly.push(
-                    *alt.addresses
-                        .get(*index as usize)
-                        .ok_or(AddressLoaderError::InvalidLookupIndex)?,
-                );
+                let Some(address) = addresses.get(*index as usize) else {
+                    #[cfg(feature = "metrics")]
+                    metrics::record_load_addresses_error("InvalidLookupIndex");
+                    return Err(AddressLoaderError::InvalidLookupIndex);
+                };
+                readonly.push(*address);
             }
         }
 
         Ok(LoadedAddresses { writable, readonly })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::address_lookup_table::state::{LookupTableMeta, ProgramState},
+    };
+
+    /// Build raw account data for an ALT with the given meta and addresses,
+    /// matching the on-chain layout `AddressLookupTable::deserialize` expects:
+    /// a bincode-encoded `ProgramState::LookupTable` header padded out to
+    /// `LOOKUP_TABLE_META_SIZE`, followed by the addresses.
+    fn encode_alt(meta: LookupTableMeta, addresses: &[Pubkey]) -> Vec<u8> {
+        let mut data = bincode::serialize(&ProgramState::LookupTable(meta)).unwrap();
+        data.resize(solana_sdk::address_lookup_table::state::LOOKUP_TABLE_META_SIZE, 0);
+        for address in addresses {
+            data.extend_from_slice(address.as_ref());
+        }
+        data
+    }
+
+    fn test_store(path: impl AsRef<Path>) -> Store {
+        let rpc_client = Arc::new(RpcClient::new_mock("succeeds".to_string()));
+        Store::load_or_create_with_mode_and_rpc_client(path, PersistMode::default(), rpc_client)
+            .unwrap()
+    }
+
+    fn tmp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "alt-store-test-{name}-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ))
+    }
+
+    #[test]
+    fn load_or_create_with_rpc_client_creates_empty_store() {
+        let path = tmp_path("load-or-create");
+        let store = test_store(&path);
+        assert!(path.exists());
+        assert!(!store.contains_key(&Pubkey::new_unique()));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn journal_replay_keeps_only_the_highest_write_version() {
+        let path = tmp_path("journal-replay");
+        let pubkey = Pubkey::new_unique();
+
+        {
+            let mut store = test_store_journal(&path);
+            store.insert_table_data(pubkey, encode_alt(LookupTableMeta::default(), &[]));
+            store.save_to_path().unwrap();
+            store.insert_table_data(
+                pubkey,
+                encode_alt(
+                    LookupTableMeta {
+                        last_extended_slot: 5,
+                        ..Default::default()
+                    },
+                    &[Pubkey::new_unique()],
+                ),
+            );
+            store.save_to_path().unwrap();
+        }
+
+        let reloaded = test_store_journal(&path);
+        assert!(reloaded.contains_key(&pubkey));
+        assert_eq!(reloaded.inner.raw.get(&pubkey).unwrap().0, 2);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    fn test_store_journal(path: impl AsRef<Path>) -> Store {
+        let rpc_client = Arc::new(RpcClient::new_mock("succeeds".to_string()));
+        Store::load_or_create_with_mode_and_rpc_client(path, PersistMode::Journal, rpc_client)
+            .unwrap()
+    }
+
+    #[test]
+    fn is_stale_detects_extension_and_deactivation_changes() {
+        let path = tmp_path("is-stale");
+        let mut store = test_store(&path);
+        let pubkey = Pubkey::new_unique();
+
+        store.insert_table_data(
+            pubkey,
+            encode_alt(
+                LookupTableMeta {
+                    last_extended_slot: 10,
+                    deactivation_slot: Slot::MAX,
+                    ..Default::default()
+                },
+                &[],
+            ),
+        );
+
+        let unchanged = encode_alt(
+            LookupTableMeta {
+                last_extended_slot: 10,
+                deactivation_slot: Slot::MAX,
+                ..Default::default()
+            },
+            &[],
+        );
+        assert!(!store.is_stale(&pubkey, &unchanged));
+
+        let extended = encode_alt(
+            LookupTableMeta {
+                last_extended_slot: 11,
+                deactivation_slot: Slot::MAX,
+                ..Default::default()
+            },
+            &[],
+        );
+        assert!(store.is_stale(&pubkey, &extended));
+
+        let deactivated = encode_alt(
+            LookupTableMeta {
+                last_extended_slot: 10,
+                deactivation_slot: 100,
+                ..Default::default()
+            },
+            &[],
+        );
+        assert!(store.is_stale(&pubkey, &deactivated));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn is_active_respects_the_deactivation_grace_period() {
+        let path = tmp_path("is-active");
+        let mut store = test_store(&path);
+
+        let never_deactivated = Pubkey::new_unique();
+        store.insert_table_data(
+            never_deactivated,
+            encode_alt(
+                LookupTableMeta {
+                    deactivation_slot: Slot::MAX,
+                    ..Default::default()
+                },
+                &[],
+            ),
+        );
+        assert!(store.is_active(&never_deactivated, 1_000_000));
+
+        let recently_deactivated = Pubkey::new_unique();
+        store.insert_table_data(
+            recently_deactivated,
+            encode_alt(
+                LookupTableMeta {
+                    deactivation_slot: 1_000,
+                    ..Default::default()
+                },
+                &[],
+            ),
+        );
+        assert!(store.is_active(&recently_deactivated, 1_000 + DEACTIVATION_GRACE_SLOTS as Slot - 1));
+        assert!(!store.is_active(&recently_deactivated, 1_000 + DEACTIVATION_GRACE_SLOTS as Slot + 1));
+
+        assert!(!store.is_active(&Pubkey::new_unique(), 1_000_000));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}